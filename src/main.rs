@@ -1,78 +1,823 @@
-use std::collections::{HashMap, VecDeque};
-use std::hash::Hash;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::time::{Duration, Instant};
 
 // Define the cache struct with a generic type that must implement the eq and hash traits.
-pub struct Cache<K, V> where K: Eq + Hash {
+// S is the HashMap's hash builder, defaulting to the standard DoS-resistant
+// RandomState; plug in a faster non-resistant hasher (e.g. FxHash/ahash) for
+// hot-path caches, or a seeded one for reproducible tests.
+pub struct Cache<K, V, S = RandomState> where K: Eq + Hash {
     // the underlying storage for cache
-    storage: HashMap<K,V>,
+    storage: HashMap<K, V, S>,
 }
 
-impl <K, V> Cache<K, V> where K: Eq + Hash + Clone {
+impl <K, V> Cache<K, V, RandomState> where K: Eq + Hash + Clone {
     // create new empty cache
     pub fn new() -> Self {
         Cache {
             storage: HashMap::new(),
         }
     }
+}
 
-    // insert a key value pair into the cache
-    pub fn set(&mut self, key: K, value: V) {
-        self.storage.insert(key, value);
+impl <K, V, S> Cache<K, V, S> where K: Eq + Hash + Clone, S: BuildHasher {
+    // create an empty cache that hashes keys with the given hasher
+    pub fn with_hasher(hasher: S) -> Self {
+        Cache {
+            storage: HashMap::with_hasher(hasher),
+        }
+    }
+
+    // insert a key value pair into the cache, returning the previous value if one was present
+    pub fn set(&mut self, key: K, value: V) -> Option<V> {
+        self.storage.insert(key, value)
     }
 
     // retrieve a value from the cache by key, returning an option
     pub fn get(&self, key: &K) -> Option<&V> {
         self.storage.get(key)
     }
+
+    // remove a key from the cache, returning its value if it was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.storage.remove(key)
+    }
+
+    // remove every entry from the cache
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    // check whether a key is present in the cache
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.storage.contains_key(key)
+    }
+
+    // number of entries currently in the cache
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    // whether the cache currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+}
+
+// implemented by arena entries that sit in an intrusive doubly-linked list, so
+// `Arena` can relink them generically without knowing their other fields.
+trait Linked {
+    fn prev(&self) -> Option<usize>;
+    fn next(&self) -> Option<usize>;
+    fn set_prev(&mut self, prev: Option<usize>);
+    fn set_next(&mut self, next: Option<usize>);
+}
+
+// an arena of `Linked` entries kept in LRU order via an intrusive
+// doubly-linked list, so touch/evict are O(1) with no scanning or key
+// cloning. Shared by LRUCache and BoundedCache, which differ only in what
+// drives eviction (capacity vs. byte budget).
+struct Arena<E> {
+    // slots are only dropped all at once by `clear`; otherwise vacated ones are recycled via `free`
+    slots: Vec<Option<E>>,
+    // vacated slots available for reuse, so `slots` doesn't grow unbounded
+    free: Vec<usize>,
+    // most recently used slot
+    head: Option<usize>,
+    // least recently used slot
+    tail: Option<usize>,
+}
+
+impl<E: Linked> Arena<E> {
+    fn new() -> Self {
+        Arena {
+            slots: Vec::new(),
+            free: Vec::new(),
+            head: None,
+            tail: None,
+        }
+    }
+
+    fn get(&self, slot: usize) -> &E {
+        self.slots[slot].as_ref().expect("occupied slot")
+    }
+
+    fn get_mut(&mut self, slot: usize) -> &mut E {
+        self.slots[slot].as_mut().expect("occupied slot")
+    }
+
+    // write a new entry into a recycled slot if one is free, otherwise grow the
+    // arena, then link it in as the most recently used
+    fn insert_front(&mut self, entry: E) -> usize {
+        let slot = if let Some(slot) = self.free.pop() {
+            self.slots[slot] = Some(entry);
+            slot
+        } else {
+            self.slots.push(Some(entry));
+            self.slots.len() - 1
+        };
+        self.push_front(slot);
+        slot
+    }
+
+    // remove a slot from the linked list without touching its own prev/next afterwards
+    fn unlink(&mut self, slot: usize) {
+        let (prev, next) = (self.get(slot).prev(), self.get(slot).next());
+        match prev {
+            Some(p) => self.get_mut(p).set_next(next),
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.get_mut(n).set_prev(prev),
+            None => self.tail = prev,
+        }
+    }
+
+    // insert a (currently unlinked) slot at the head of the list
+    fn push_front(&mut self, slot: usize) {
+        let old_head = self.head;
+        self.get_mut(slot).set_prev(None);
+        self.get_mut(slot).set_next(old_head);
+        if let Some(old_head) = old_head {
+            self.get_mut(old_head).set_prev(Some(slot));
+        }
+        self.head = Some(slot);
+        if self.tail.is_none() {
+            self.tail = Some(slot);
+        }
+    }
+
+    fn move_to_front(&mut self, slot: usize) {
+        if self.head == Some(slot) {
+            return;
+        }
+        self.unlink(slot);
+        self.push_front(slot);
+    }
+
+    // unlink and free a known slot, returning its entry
+    fn remove(&mut self, slot: usize) -> E {
+        self.unlink(slot);
+        let entry = self.slots[slot].take().expect("occupied slot");
+        self.free.push(slot);
+        entry
+    }
+
+    // unlink and free the least recently used slot, returning its entry
+    fn evict_tail(&mut self) -> Option<E> {
+        let slot = self.tail?;
+        Some(self.remove(slot))
+    }
+
+    fn clear(&mut self) {
+        self.slots.clear();
+        self.free.clear();
+        self.head = None;
+        self.tail = None;
+    }
+
+    // walk entries from most to least recently used
+    fn iter(&self) -> ArenaIter<'_, E> {
+        ArenaIter {
+            slots: &self.slots,
+            current: self.head,
+        }
+    }
+
+    // like iter, but yields mutable references
+    fn iter_mut(&mut self) -> ArenaIterMut<'_, E> {
+        ArenaIterMut {
+            slots: self.slots.as_mut_ptr(),
+            current: self.head,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+struct ArenaIter<'a, E> {
+    slots: &'a [Option<E>],
+    current: Option<usize>,
+}
+
+impl<'a, E: Linked> Iterator for ArenaIter<'a, E> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = self.current?;
+        let entry = self.slots[slot].as_ref().expect("occupied slot");
+        self.current = entry.next();
+        Some(entry)
+    }
+}
+
+struct ArenaIterMut<'a, E> {
+    slots: *mut Option<E>,
+    current: Option<usize>,
+    _marker: std::marker::PhantomData<&'a mut E>,
+}
+
+impl<'a, E: Linked> Iterator for ArenaIterMut<'a, E> {
+    type Item = &'a mut E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot = self.current?;
+        // safe: each slot in the list is visited at most once per iteration,
+        // so no two `next()` calls ever alias the same entry
+        let entry = unsafe { (*self.slots.add(slot)).as_mut().expect("occupied slot") };
+        self.current = entry.next();
+        Some(entry)
+    }
+}
+
+// a single slot in an LRUCache's arena
+struct CacheEntry<K, V> {
+    key: K,
+    value: V,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<K, V> Linked for CacheEntry<K, V> {
+    fn prev(&self) -> Option<usize> { self.prev }
+    fn next(&self) -> Option<usize> { self.next }
+    fn set_prev(&mut self, prev: Option<usize>) { self.prev = prev; }
+    fn set_next(&mut self, next: Option<usize>) { self.next = next; }
 }
 
-pub struct LRUCache<K, V> where K: Eq + Hash + Clone, {
+pub struct LRUCache<K, V, S = RandomState> where K: Eq + Hash + Clone, {
     capacity: usize,
-    storage: HashMap<K, V>,
-    usage_order: VecDeque<K>,
+    arena: Arena<CacheEntry<K, V>>,
+    // maps a key to its slot in `arena`
+    index: HashMap<K, usize, S>,
 }
 
-impl<K, V> LRUCache<K, V> where K: Eq + Hash + Clone, {
+impl<K, V> LRUCache<K, V, RandomState> where K: Eq + Hash + Clone, {
 
     pub fn new(capacity: usize) -> Self<> {
         assert!(capacity > 0, "cache capacity must be greater than 0");
         LRUCache {
             capacity,
+            arena: Arena::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<K, V, S> LRUCache<K, V, S> where K: Eq + Hash + Clone, S: BuildHasher {
+
+    // create an empty cache that hashes keys with the given hasher
+    pub fn with_hasher(capacity: usize, hasher: S) -> Self {
+        assert!(capacity > 0, "cache capacity must be greater than 0");
+        LRUCache {
+            capacity,
+            arena: Arena::new(),
+            index: HashMap::with_hasher(hasher),
+        }
+    }
+
+    // insert or update an entry, returning the previous value if the key was already present
+    pub fn set(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(&slot) = self.index.get(&key) {
+            // key already present: update in place and bump to the front
+            let previous = std::mem::replace(&mut self.arena.get_mut(slot).value, value);
+            self.arena.move_to_front(slot);
+            return Some(previous);
+        }
+
+        let slot = self.arena.insert_front(CacheEntry { key: key.clone(), value, prev: None, next: None });
+        self.index.insert(key, slot);
+
+        // if cache exceeds its capacity, remove the least recently used item.
+        if self.index.len() > self.capacity {
+            if let Some(evicted) = self.arena.evict_tail() {
+                self.index.remove(&evicted.key);
+            }
+        }
+        None
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let slot = *self.index.get(key)?;
+        // move this key to the front of the usage order
+        self.arena.move_to_front(slot);
+        Some(&self.arena.get(slot).value)
+    }
+
+    // read a value without promoting it in the usage order
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let &slot = self.index.get(key)?;
+        Some(&self.arena.get(slot).value)
+    }
+
+    // check whether a key is present, without promoting it in the usage order
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    // remove an entry, returning its value if the key was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.index.remove(key)?;
+        Some(self.arena.remove(slot).value)
+    }
+
+    // remove every entry from the cache
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.index.clear();
+    }
+
+    // number of entries currently in the cache
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    // whether the cache currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    // iterate over entries from most to least recently used
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.arena.iter() }
+    }
+
+    // iterate mutably over entries from most to least recently used
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { inner: self.arena.iter_mut() }
+    }
+}
+
+// walks an LRUCache's entries from most to least recently used
+pub struct Iter<'a, K, V> {
+    inner: ArenaIter<'a, CacheEntry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+// like Iter, but yields mutable references to the values
+pub struct IterMut<'a, K, V> {
+    inner: ArenaIterMut<'a, CacheEntry<K, V>>,
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &mut entry.value))
+    }
+}
+
+#[cfg(test)]
+mod lru_cache_tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LRUCache::new(2);
+        cache.set("a", 1);
+        cache.set("b", 2);
+        cache.get(&"a"); // promotes "a", leaving "b" as the least recently used
+        cache.set("c", 3); // evicts "b"
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(&1));
+        assert_eq!(cache.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn capacity_one_thrashes_without_growing_the_arena() {
+        let mut cache = LRUCache::new(1);
+        for i in 0..50 {
+            cache.set(i, i * 10);
+            assert_eq!(cache.get(&i), Some(&(i * 10)));
+            if i > 0 {
+                assert_eq!(cache.get(&(i - 1)), None);
+            }
+        }
+
+        // the arena grows by one slot past capacity once (insertion happens
+        // before the resulting overflow is evicted), then that freed slot is
+        // recycled for every later set instead of the arena growing further.
+        assert_eq!(cache.arena.slots.len(), 2);
+    }
+
+    #[test]
+    fn remove_then_reinsert_reuses_the_freed_slot() {
+        let mut cache = LRUCache::new(2);
+        cache.set("a", 1);
+        cache.set("b", 2);
+        cache.remove(&"a");
+        assert_eq!(cache.arena.free.len(), 1);
+
+        cache.set("c", 3);
+        assert_eq!(cache.arena.free.len(), 0);
+        assert_eq!(cache.arena.slots.len(), 2);
+        assert_eq!(cache.get(&"c"), Some(&3));
+        assert_eq!(cache.get(&"b"), Some(&2));
+    }
+
+    #[test]
+    fn iter_mut_mutations_are_visible_through_get() {
+        let mut cache = LRUCache::new(2);
+        cache.set("a", 1);
+        cache.set("b", 2);
+        for (_, value) in cache.iter_mut() {
+            *value *= 10;
+        }
+
+        assert_eq!(cache.get(&"a"), Some(&10));
+        assert_eq!(cache.get(&"b"), Some(&20));
+    }
+}
+
+// a cache whose entries expire a fixed duration after they were inserted
+pub struct TimedCache<K, V> where K: Eq + Hash {
+    storage: HashMap<K, (V, Instant)>,
+    lifespan: Duration,
+    // if true, a successful `get` resets the entry's insertion time
+    refresh_on_read: bool,
+}
+
+impl<K, V> TimedCache<K, V> where K: Eq + Hash {
+    // create a cache whose entries expire `lifespan` after being inserted
+    pub fn with_lifespan(lifespan: Duration) -> Self {
+        TimedCache {
+            storage: HashMap::new(),
+            lifespan,
+            refresh_on_read: false,
+        }
+    }
+
+    // like `with_lifespan`, but a hit on `get` resets the entry's expiry instead of letting it lapse
+    pub fn with_lifespan_and_refresh(lifespan: Duration) -> Self {
+        TimedCache {
             storage: HashMap::new(),
-            usage_order: VecDeque::new(),
+            lifespan,
+            refresh_on_read: true,
         }
     }
 
     pub fn set(&mut self, key: K, value: V) {
-        // insert or update the value for the key
-        self.storage.insert(key.clone(), value);
-        // move this key to the front of the usage order to mark it as recently used
-        self.update_usage(&key);
-        // if cache exceeds its capacity, remove the least recently used item.
-        if self.storage.len() > self.capacity {
-            if let Some(least_recently_used) = self.usage_order.pop_back() {
-                self.storage.remove(&least_recently_used);
+        self.storage.insert(key, (value, Instant::now()));
+    }
+
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let expired = match self.storage.get(key) {
+            Some((_, inserted_at)) => inserted_at.elapsed() >= self.lifespan,
+            None => return None,
+        };
+
+        if expired {
+            self.storage.remove(key);
+            return None;
+        }
+
+        if self.refresh_on_read {
+            self.storage.get_mut(key).unwrap().1 = Instant::now();
+        }
+        self.storage.get(key).map(|(value, _)| value)
+    }
+
+    // remove an entry, returning its value if the key was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.storage.remove(key).map(|(value, _)| value)
+    }
+
+    // remove every entry from the cache
+    pub fn clear(&mut self) {
+        self.storage.clear();
+    }
+
+    // check whether a non-expired entry is present, lazily evicting it if expired
+    pub fn contains_key(&mut self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    // number of entries currently stored, including any not yet lazily expired
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    // whether the cache currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod timed_cache_tests {
+    use super::*;
+
+    #[test]
+    fn entry_expires_after_lifespan_elapses() {
+        let mut cache = TimedCache::with_lifespan(Duration::from_millis(20));
+        cache.set("a", 1);
+        assert_eq!(cache.get(&"a"), Some(&1));
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(cache.get(&"a"), None);
+    }
+
+    #[test]
+    fn refresh_on_read_resets_the_clock() {
+        let mut cache = TimedCache::with_lifespan_and_refresh(Duration::from_millis(30));
+        cache.set("a", 1);
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get(&"a"), Some(&1)); // hit resets the insertion time
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(cache.get(&"a"), Some(&1)); // would be expired without the refresh above
+    }
+}
+
+// reports a value's approximate size in bytes, including any heap allocation.
+// implement it for your own types by summing the struct's stack size with the
+// heap footprint of each field, e.g. for an image:
+//
+//     struct Image { pixels: Vec<u8>, width: u32, height: u32 }
+//     impl MemSize for Image {
+//         fn mem_size(&self) -> usize {
+//             std::mem::size_of::<Image>() + self.pixels.capacity()
+//         }
+//     }
+pub trait MemSize {
+    fn mem_size(&self) -> usize;
+}
+
+impl MemSize for String {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<String>() + self.capacity()
+    }
+}
+
+impl MemSize for Vec<u8> {
+    fn mem_size(&self) -> usize {
+        std::mem::size_of::<Vec<u8>>() + self.capacity()
+    }
+}
+
+// plain fixed-size types have no heap footprint beyond their own bytes
+macro_rules! impl_mem_size_for_sized {
+    ($($t:ty),*) => {
+        $(impl MemSize for $t {
+            fn mem_size(&self) -> usize {
+                std::mem::size_of::<$t>()
+            }
+        })*
+    };
+}
+impl_mem_size_for_sized!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char);
+
+// a single slot in a BoundedCache's arena; tracks its own contribution to
+// current_size so eviction can be driven by bytes rather than count.
+struct BoundedEntry<K, V> {
+    key: K,
+    value: V,
+    size: usize,
+    prev: Option<usize>,
+    next: Option<usize>,
+}
+
+impl<K, V> Linked for BoundedEntry<K, V> {
+    fn prev(&self) -> Option<usize> { self.prev }
+    fn next(&self) -> Option<usize> { self.next }
+    fn set_prev(&mut self, prev: Option<usize>) { self.prev = prev; }
+    fn set_next(&mut self, next: Option<usize>) { self.next = next; }
+}
+
+// an LRU cache bounded by total estimated memory rather than entry count,
+// for workloads where values vary wildly in size (strings, Vecs, images).
+pub struct BoundedCache<K, V> where K: Eq + Hash + Clone, V: MemSize {
+    max_bytes: usize,
+    current_size: usize,
+    arena: Arena<BoundedEntry<K, V>>,
+    // maps a key to its slot in `arena`
+    index: HashMap<K, usize>,
+}
+
+impl<K, V> BoundedCache<K, V> where K: Eq + Hash + Clone, V: MemSize {
+
+    pub fn new(max_bytes: usize) -> Self {
+        assert!(max_bytes > 0, "cache byte budget must be greater than 0");
+        BoundedCache {
+            max_bytes,
+            current_size: 0,
+            arena: Arena::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn set(&mut self, key: K, value: V) {
+        let size = value.mem_size();
+
+        if let Some(&slot) = self.index.get(&key) {
+            // key already present: update in place, accounting for the size delta
+            self.current_size -= self.arena.get(slot).size;
+            let entry = self.arena.get_mut(slot);
+            entry.value = value;
+            entry.size = size;
+            self.current_size += size;
+            self.arena.move_to_front(slot);
+        } else {
+            let slot = self.arena.insert_front(BoundedEntry { key: key.clone(), value, size, prev: None, next: None });
+            self.index.insert(key, slot);
+            self.current_size += size;
+        }
+
+        // evict the least recently used entry until back under budget, but
+        // never against the only entry left
+        while self.current_size > self.max_bytes && self.index.len() > 1 {
+            if let Some(evicted) = self.arena.evict_tail() {
+                self.index.remove(&evicted.key);
+                self.current_size -= evicted.size;
             }
         }
     }
 
     pub fn get(&mut self, key: &K) -> Option<&V> {
-        if self.storage.contains_key(key) {
-            // move this key to the front of the usage order
-            self.update_usage(key);
-            self.storage.get(key)
+        let slot = *self.index.get(key)?;
+        self.arena.move_to_front(slot);
+        Some(&self.arena.get(slot).value)
+    }
+
+    // read a value without promoting it in the usage order
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let &slot = self.index.get(key)?;
+        Some(&self.arena.get(slot).value)
+    }
+
+    // check whether a key is present, without promoting it in the usage order
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    // remove an entry, returning its value if the key was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let slot = self.index.remove(key)?;
+        let entry = self.arena.remove(slot);
+        self.current_size -= entry.size;
+        Some(entry.value)
+    }
+
+    // remove every entry from the cache
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.index.clear();
+        self.current_size = 0;
+    }
+
+    // number of entries currently in the cache
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    // whether the cache currently holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod bounded_cache_tests {
+    use super::*;
+
+    #[test]
+    fn evicts_least_recently_used_once_over_budget() {
+        // each 4-byte string costs size_of::<String>() + 4 = 28 bytes; a budget
+        // of 60 fits two such entries (56) but not three (84)
+        let mut cache: BoundedCache<&str, String> = BoundedCache::new(60);
+        cache.set("a", "1111".to_string());
+        cache.set("b", "2222".to_string());
+        cache.get(&"a"); // promotes "a", leaving "b" as the least recently used
+        cache.set("c", "3333".to_string()); // evicts "b" to stay under budget
+
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"a"), Some(&"1111".to_string()));
+        assert_eq!(cache.get(&"c"), Some(&"3333".to_string()));
+    }
+
+    #[test]
+    fn single_entry_over_budget_is_kept_rather_than_evicted_against_itself() {
+        let mut cache: BoundedCache<&str, String> = BoundedCache::new(8);
+        cache.set("a", "oversized".repeat(4)); // far larger than the 8 byte budget
+
+        // the only entry is never evicted against itself, so it's kept despite
+        // leaving the cache permanently over budget
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(&"a"), Some(&"oversized".repeat(4)));
+    }
+}
+
+// a fixed-capacity LRU cache with values stored inline in `slots`; LRU order
+// is tracked by an index-based linked list over the array, so reordering
+// never moves the values themselves.
+pub struct ArrayLRUCache<T, const N: usize> {
+    slots: [Option<T>; N],
+    next: [Option<usize>; N],
+    prev: [Option<usize>; N],
+    // most recently used slot
+    head: Option<usize>,
+    // least recently used slot
+    tail: Option<usize>,
+    // number of slots that have ever been occupied; once this reaches N,
+    // inserts recycle the LRU slot instead of growing into a fresh one
+    len: usize,
+}
+
+impl<T, const N: usize> Default for ArrayLRUCache<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> ArrayLRUCache<T, N> {
+    pub fn new() -> Self {
+        assert!(N > 0, "ArrayLRUCache capacity N must be greater than 0");
+        ArrayLRUCache {
+            slots: std::array::from_fn(|_| None),
+            next: [None; N],
+            prev: [None; N],
+            head: None,
+            tail: None,
+            len: 0,
+        }
+    }
+
+    // insert a value as the most recently used entry, evicting the least
+    // recently used one first if the array is already full
+    pub fn insert(&mut self, value: T) {
+        let slot = if self.len < N {
+            let slot = self.len;
+            self.len += 1;
+            slot
         } else {
-            None
+            let lru = self.tail.expect("array has capacity N > 0 once full");
+            self.unlink(lru);
+            lru
+        };
+        self.slots[slot] = Some(value);
+        self.push_front(slot);
+    }
+
+    // scan for the first entry matching `predicate`, promoting it to most
+    // recently used on a hit
+    pub fn lookup<F>(&mut self, mut predicate: F) -> Option<&T>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        let slot = (0..N).find(|&slot| {
+            self.slots[slot].as_ref().is_some_and(&mut predicate)
+        })?;
+        self.touch(slot);
+        self.slots[slot].as_ref()
+    }
+
+    // promote an already-known slot to most recently used
+    fn touch(&mut self, slot: usize) {
+        if self.slots[slot].is_some() {
+            self.move_to_front(slot);
         }
     }
 
-    pub fn update_usage(&mut self, key: &K) {
-        // remove key if it already exists in usage order
-        self.usage_order.retain(|existing_key| existing_key != key);
-            // insert the key at the front to mark it as recently used
-            self.usage_order.push_front(key.clone());
+    fn unlink(&mut self, slot: usize) {
+        let (prev, next) = (self.prev[slot], self.next[slot]);
+        match prev {
+            Some(p) => self.next[p] = next,
+            None => self.head = next,
+        }
+        match next {
+            Some(n) => self.prev[n] = prev,
+            None => self.tail = prev,
+        }
+    }
+
+    fn push_front(&mut self, slot: usize) {
+        self.prev[slot] = None;
+        self.next[slot] = self.head;
+        if let Some(old_head) = self.head {
+            self.prev[old_head] = Some(slot);
+        }
+        self.head = Some(slot);
+        if self.tail.is_none() {
+            self.tail = Some(slot);
+        }
     }
 
+    fn move_to_front(&mut self, slot: usize) {
+        if self.head == Some(slot) {
+            return;
+        }
+        self.unlink(slot);
+        self.push_front(slot);
+    }
 }
 
 fn main() {
@@ -108,4 +853,49 @@ fn main() {
         None => println!("key2 was evicted"),
 
     }
+
+    // example usage of TimedCache
+    let mut timed_cache = TimedCache::with_lifespan(Duration::from_millis(50));
+    timed_cache.set("key1", "value1");
+    println!("Retrieved: {:?}", timed_cache.get(&"key1")); // should still be fresh
+    std::thread::sleep(Duration::from_millis(60));
+    match timed_cache.get(&"key1") {
+        Some(value) => println!("Retrieved: {:?}", value),
+        None => println!("key1 expired"),
+    }
+
+    // example usage of BoundedCache
+    let mut bounded_cache: BoundedCache<&str, String> = BoundedCache::new(32);
+    bounded_cache.set("key1", "a".repeat(16));
+    bounded_cache.set("key2", "b".repeat(16)); // pushes key1 out once the budget is exceeded
+    println!("Retrieved: {:?}", bounded_cache.get(&"key1"));
+    println!("Retrieved: {:?}", bounded_cache.get(&"key2"));
+
+    // example usage of LRUCache with a custom hasher
+    let mut seeded_lrucache = LRUCache::with_hasher(2, RandomState::new());
+    seeded_lrucache.set("key1", "value1");
+    println!("Retrieved: {:?}", seeded_lrucache.get(&"key1"));
+
+    // example usage of ArrayLRUCache
+    let mut array_lrucache: ArrayLRUCache<&str, 2> = ArrayLRUCache::new();
+    array_lrucache.insert("value1");
+    array_lrucache.insert("value2");
+    println!("Retrieved: {:?}", array_lrucache.lookup(|v| *v == "value1")); // promotes value1
+    array_lrucache.insert("value3"); // evicts value2, the now-least-recently-used entry
+    println!("Retrieved: {:?}", array_lrucache.lookup(|v| *v == "value2"));
+
+    // example usage of the rounded-out LRUCache map API
+    let mut map_cache = LRUCache::new(3);
+    map_cache.set("key1", "value1");
+    map_cache.set("key2", "value2");
+    println!("Previous value: {:?}", map_cache.set("key1", "value1-updated"));
+    println!("Peek without touching order: {:?}", map_cache.peek(&"key2"));
+    println!("Contains key2: {}", map_cache.contains_key(&"key2"));
+    println!("Removed: {:?}", map_cache.remove(&"key2"));
+    println!("Length after removal: {}", map_cache.len());
+    for (key, value) in map_cache.iter() {
+        println!("In MRU order: {} => {}", key, value);
+    }
+    map_cache.clear();
+    println!("Empty after clear: {}", map_cache.is_empty());
 }
\ No newline at end of file